@@ -1,35 +1,71 @@
-use reqwest::{header::HeaderMap, ClientBuilder};
-use std::error::Error;
-use std::collections::HashMap;
+use reqwest::header::HeaderMap;
+use std::collections::{HashMap, VecDeque};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Duration;
 use serde::{Serialize, Deserialize};
-use std::sync::atomic::{AtomicU16, AtomicI64, Ordering};
+use std::sync::atomic::{AtomicU16, AtomicI64, AtomicBool, Ordering};
+use futures::Stream;
+use thiserror::Error;
+use tokio::sync::mpsc;
 
 /// API 域名前缀.
 pub const V2EX_API_DOMAIN: &str = "https://www.v2ex.com/api/v2";
 
-#[derive(Debug)]
+/// 本库所有可能失败的操作所返回的错误类型.
+#[derive(Error, Debug)]
+pub enum ApiError {
+    /// 底层 HTTP 请求失败, 例如连接错误或请求构建失败.
+    #[error("http request failed: {0}")]
+    Http(#[from] reqwest::Error),
+
+    /// 响应体不是预期的 JSON 结构.
+    #[error("failed to decode response body: {0}")]
+    Decode(#[from] serde_json::Error),
+
+    /// 请求头的值不合法, 例如 token 中包含无法放入 HTTP 头的字符.
+    #[error("invalid header value: {0}")]
+    InvalidHeader(#[from] reqwest::header::InvalidHeaderValue),
+
+    /// 请求被限流, `reset` 为达到下一个时间窗口所需等待的秒数.
+    #[error("rate limited, retry after {reset}s")]
+    RateLimited { reset: i64 },
+
+    /// V2EX 接口返回了 `success: false`.
+    #[error("v2ex api error: {message}")]
+    Api { message: String },
+}
+
+#[derive(Debug, Clone)]
 pub struct Client {
     req_client: reqwest::Client,
-    limit: AtomicU16,
-    remaining: AtomicU16,
-    reset: AtomicI64,
+    base_url: String,
+    limit: Arc<AtomicU16>,
+    remaining: Arc<AtomicU16>,
+    reset: Arc<AtomicI64>,
+    rate_limiting: Arc<AtomicBool>,
 }
 
 impl Client {
     pub fn new(token: &str) -> Client {
-        let mut bearer = String::from("Bearer ");
-        bearer.push_str(token);
+        ClientBuilder::new().token(token).build().unwrap()
+    }
 
-        let mut hm = HeaderMap::new();
-        hm.append("Authorization", bearer.parse().unwrap());
+    /// 创建一个可链式配置 base URL、超时、自定义 reqwest 客户端等选项的构建器.
+    pub fn builder() -> ClientBuilder {
+        ClientBuilder::new()
+    }
 
-        let cb = ClientBuilder::new();
-        Client {
-            req_client: cb.default_headers(hm).build().unwrap(),
-            limit: AtomicU16::new(0),
-            remaining: AtomicU16::new(0),
-            reset: AtomicI64::new(0),
-        }
+    /// 开启或关闭基于速率限制响应头的自动节流与退避, 默认关闭.
+    ///
+    /// 开启后, 在 `remaining` 降为 0 或收到 429 响应时, 请求会自动等待到
+    /// `reset` 指示的时间点再继续. 收到 429 时只重试一次, 若重试仍为 429,
+    /// 则返回 [`ApiError::RateLimited`].
+    pub fn with_rate_limiting(self, enabled: bool) -> Client {
+        self.rate_limiting.store(enabled, Ordering::Relaxed);
+        self
     }
 
     fn set_rate(&self, header: &reqwest::header::HeaderMap) {
@@ -58,6 +94,45 @@ impl Client {
         }
     }
 
+    /// 在速率限制的约束下执行请求: 若已开启节流且 `remaining` 为 0, 先等待到
+    /// `reset` 再发送; 若已开启节流且响应为 429, 再等待一次并重试一次.
+    /// 未开启节流时, 429 响应原样返回.
+    async fn throttled_execute(&self, req: reqwest::Request) -> Result<reqwest::Response, ApiError> {
+        if self.rate_limiting.load(Ordering::Relaxed) && self.remaining.load(Ordering::Relaxed) == 0 {
+            self.sleep_until_reset().await;
+        }
+
+        let retry = req.try_clone();
+        let rsp = self.req_client.execute(req).await?;
+        self.set_rate(rsp.headers());
+
+        if rsp.status() == reqwest::StatusCode::TOO_MANY_REQUESTS {
+            if self.rate_limiting.load(Ordering::Relaxed) {
+                if let Some(retry) = retry {
+                    self.sleep_until_reset().await;
+                    let rsp = self.req_client.execute(retry).await?;
+                    self.set_rate(rsp.headers());
+                    if rsp.status() == reqwest::StatusCode::TOO_MANY_REQUESTS {
+                        return Err(ApiError::RateLimited { reset: self.reset.load(Ordering::Relaxed) });
+                    }
+                    return Ok(rsp);
+                }
+                return Err(ApiError::RateLimited { reset: self.reset.load(Ordering::Relaxed) });
+            } else {
+                return Ok(rsp);
+            }
+        }
+
+        Ok(rsp)
+    }
+
+    async fn sleep_until_reset(&self) {
+        let wait = self.reset.load(Ordering::Relaxed);
+        if wait > 0 {
+            tokio::time::sleep(std::time::Duration::from_secs(wait as u64)).await;
+        }
+    }
+
     /// 同一个时间段所允许的请求的最大数目.
     pub fn limit(&self) -> u16 {
         self.limit.load(Ordering::Relaxed)
@@ -74,168 +149,481 @@ impl Client {
     }
 
     /// 获取最新的提醒.
-    pub async fn get_notifications(&self, req: &GetNotificationsReq) -> Result<GetNotificationsRsp, Box<dyn Error>> {
+    pub async fn get_notifications(&self, req: &GetNotificationsReq) -> Result<GetNotificationsRsp, ApiError> {
         let mut page = req.page;
         if page <= 0 {
             page = 1
         }
 
-        let url = format!("{}{}", V2EX_API_DOMAIN, "/notifications");
+        let url = format!("{}{}", self.base_url, "/notifications");
         let req = self.req_client.get(url)
             .query(&[("p", page)])
             .build()?;
 
         // println!("url: {:?}", req.url().to_string());
 
-        let rsp = self.req_client.execute(req).await?;
-        self.set_rate(rsp.headers());
+        let rsp = self.throttled_execute(req).await?;
 
         let bytes = rsp.bytes().await?;
-        let body = serde_json::from_slice(&bytes)?;
+        let body = serde_json::from_slice::<GetNotificationsRsp>(&bytes)?;
+        if !body.status.success {
+            return Err(ApiError::Api { message: body.status.message });
+        }
         Ok(body)
     }
 
+    /// 以流的形式持续获取提醒, 按需自动翻页.
+    pub fn get_notifications_stream(&self) -> Pages<Notification> {
+        let client = self.clone();
+        Pages::new(client, |client, page| {
+            Box::pin(async move {
+                let rsp = client.get_notifications(&GetNotificationsReq { page }).await?;
+                Ok(rsp.result)
+            })
+        })
+    }
+
     /// 删除指定的提醒.
-    pub async fn delete_notification(&self, req: &DeleteNotificationReq) -> Result<DeleteNotificationRsp, Box<dyn Error>> {
-        let url = format!("{}/notifications/{}", V2EX_API_DOMAIN, req.notification_id);
+    pub async fn delete_notification(&self, req: &DeleteNotificationReq) -> Result<DeleteNotificationRsp, ApiError> {
+        let url = format!("{}/notifications/{}", self.base_url, req.notification_id);
         let req = self.req_client.delete(url)
             .build()?;
 
         // println!("url: {:?}", req.url().to_string());
 
-        let rsp = self.req_client.execute(req).await?;
-        self.set_rate(rsp.headers());
+        let rsp = self.throttled_execute(req).await?;
 
         let bytes = rsp.bytes().await?;
-        let body = serde_json::from_slice(&bytes)?;
+        let body = serde_json::from_slice::<DeleteNotificationRsp>(&bytes)?;
+        if !body.status.success {
+            return Err(ApiError::Api { message: body.status.message });
+        }
         Ok(body)
     }
 
     /// 获取自己的 Profile.
-    pub async fn get_member(&self) -> Result<GetMemberRsp, Box<dyn Error>> {
-        let url = format!("{}{}", V2EX_API_DOMAIN, "/member");
+    pub async fn get_member(&self) -> Result<GetMemberRsp, ApiError> {
+        let url = format!("{}{}", self.base_url, "/member");
         let req = self.req_client.get(url).build()?;
 
         // println!("url: {:?}", req.url().to_string());
 
-        let rsp = self.req_client.execute(req).await?;
-        self.set_rate(rsp.headers());
+        let rsp = self.throttled_execute(req).await?;
 
         let bytes = rsp.bytes().await?;
-        let body = serde_json::from_slice(&bytes)?;
+        let body = serde_json::from_slice::<GetMemberRsp>(&bytes)?;
+        if !body.success {
+            return Err(ApiError::Api { message: "v2ex api returned success=false".to_string() });
+        }
         Ok(body)
     }
 
     /// 查看当前使用的令牌.
-    pub async fn get_token(&self) -> Result<GetTokenRsp, Box<dyn Error>> {
-        let url = format!("{}{}", V2EX_API_DOMAIN, "/token");
+    pub async fn get_token(&self) -> Result<GetTokenRsp, ApiError> {
+        let url = format!("{}{}", self.base_url, "/token");
         let req = self.req_client.get(url).build()?;
 
         // println!("url: {:?}", req.url().to_string());
 
-        let rsp = self.req_client.execute(req).await?;
-        self.set_rate(rsp.headers());
+        let rsp = self.throttled_execute(req).await?;
 
         let bytes = rsp.bytes().await?;
-        let body = serde_json::from_slice(&bytes)?;
+        let body = serde_json::from_slice::<GetTokenRsp>(&bytes)?;
+        if !body.status.success {
+            return Err(ApiError::Api { message: body.status.message });
+        }
         Ok(body)
     }
 
     /// 创建新的令牌.
     /// 在系统中最多创建 10 个 Personal Access Token.
-    pub async fn post_token(&self, req: &PostTokenReq) -> Result<PostTokenRsp, Box<dyn Error>> {
+    pub async fn post_token(&self, req: &PostTokenReq) -> Result<PostTokenRsp, ApiError> {
         let mut data = HashMap::new();
         data.insert("scope", req.scope.as_str());
         data.insert("expiration", req.expiration.as_str());
 
-        let url = format!("{}{}", V2EX_API_DOMAIN, "/tokens");
+        let url = format!("{}{}", self.base_url, "/tokens");
         let req = self.req_client.post(url)
             .json(&data)
             .build()?;
 
         // println!("url: {:?}", req.url().to_string());
 
-        let rsp = self.req_client.execute(req).await?;
-        self.set_rate(rsp.headers());
+        let rsp = self.throttled_execute(req).await?;
 
         let bytes = rsp.bytes().await?;
-        let body = serde_json::from_slice(&bytes)?;
+        let body = serde_json::from_slice::<PostTokenRsp>(&bytes)?;
+        if !body.success {
+            return Err(ApiError::Api { message: "v2ex api returned success=false".to_string() });
+        }
         Ok(body)
     }
 
     /// 获取指定节点.
-    pub async fn get_node(&self, req: &GetNodeReq) -> Result<GetNodeRsp, Box<dyn Error>> {
-        let url = format!("{}/nodes/{}", V2EX_API_DOMAIN, req.node_name);
+    pub async fn get_node(&self, req: &GetNodeReq) -> Result<GetNodeRsp, ApiError> {
+        let url = format!("{}/nodes/{}", self.base_url, req.node_name);
         let req = self.req_client.get(url).build()?;
 
         // println!("url: {:?}", req.url().to_string());
 
-        let rsp = self.req_client.execute(req).await?;
-        self.set_rate(rsp.headers());
+        let rsp = self.throttled_execute(req).await?;
 
         let bytes = rsp.bytes().await?;
-        let body = serde_json::from_slice(&bytes)?;
+        let body = serde_json::from_slice::<GetNodeRsp>(&bytes)?;
+        if !body.status.success {
+            return Err(ApiError::Api { message: body.status.message });
+        }
         Ok(body)
     }
 
     /// 获取指定节点下的主题.
-    pub async fn get_node_topics(&self, req: &GetNodeTopicsReq) -> Result<GetNodeTopicsRsp, Box<dyn Error>> {
+    pub async fn get_node_topics(&self, req: &GetNodeTopicsReq) -> Result<GetNodeTopicsRsp, ApiError> {
         let mut page = req.page;
         if page <= 0 {
             page = 1
         }
 
-        let url = format!("{}/nodes/{}/topics", V2EX_API_DOMAIN, req.node_name);
+        let url = format!("{}/nodes/{}/topics", self.base_url, req.node_name);
         let req = self.req_client.get(url)
             .query(&[("p", page)])
             .build()?;
 
         // println!("url: {:?}", req.url().to_string());
 
-        let rsp = self.req_client.execute(req).await?;
-        self.set_rate(rsp.headers());
+        let rsp = self.throttled_execute(req).await?;
 
         let bytes = rsp.bytes().await?;
-        let body = serde_json::from_slice(&bytes)?;
+        let body = serde_json::from_slice::<GetNodeTopicsRsp>(&bytes)?;
+        if !body.status.success {
+            return Err(ApiError::Api { message: body.status.message });
+        }
         Ok(body)
     }
 
+    /// 以流的形式持续获取指定节点下的主题, 按需自动翻页.
+    pub fn get_node_topics_stream(&self, node_name: &str) -> Pages<NodeTopic> {
+        let client = self.clone();
+        let node_name = node_name.to_string();
+        Pages::new(client, move |client, page| {
+            let node_name = node_name.clone();
+            Box::pin(async move {
+                let rsp = client.get_node_topics(&GetNodeTopicsReq { node_name, page }).await?;
+                Ok(rsp.result)
+            })
+        })
+    }
+
     /// 获取指定主题.
-    pub async fn get_topic(&self, req: &GetTopicReq) -> Result<GetTopicRsp, Box<dyn Error>> {
-        let url = format!("{}/topics/{}", V2EX_API_DOMAIN, req.topic_id);
+    pub async fn get_topic(&self, req: &GetTopicReq) -> Result<GetTopicRsp, ApiError> {
+        let url = format!("{}/topics/{}", self.base_url, req.topic_id);
         let req = self.req_client.get(url).build()?;
 
         // println!("url: {:?}", req.url().to_string());
 
-        let rsp = self.req_client.execute(req).await?;
-        self.set_rate(rsp.headers());
+        let rsp = self.throttled_execute(req).await?;
 
         let bytes = rsp.bytes().await?;
-        let body = serde_json::from_slice(&bytes)?;
+        let body = serde_json::from_slice::<GetTopicRsp>(&bytes)?;
+        if !body.status.success {
+            return Err(ApiError::Api { message: body.status.message });
+        }
         Ok(body)
     }
 
     /// 获取指定主题下的回复.
-    pub async fn get_topic_replies(&self, req: &GetTopicRepliesReq) -> Result<GetTopicRepliesRsp, Box<dyn Error>> {
+    pub async fn get_topic_replies(&self, req: &GetTopicRepliesReq) -> Result<GetTopicRepliesRsp, ApiError> {
         let mut page = req.page;
         if page <= 0 {
             page = 1
         }
 
-        let url = format!("{}/topics/{}/replies", V2EX_API_DOMAIN, req.topic_id);
+        let url = format!("{}/topics/{}/replies", self.base_url, req.topic_id);
         let req = self.req_client.get(url)
             .query(&[("p", page)])
             .build()?;
 
         // println!("url: {:?}", req.url().to_string());
 
-        let rsp = self.req_client.execute(req).await?;
-        self.set_rate(rsp.headers());
+        let rsp = self.throttled_execute(req).await?;
 
         let bytes = rsp.bytes().await?;
-        let body = serde_json::from_slice(&bytes)?;
+        let body = serde_json::from_slice::<GetTopicRepliesRsp>(&bytes)?;
+        if !body.status.success {
+            return Err(ApiError::Api { message: body.status.message });
+        }
         Ok(body)
     }
+
+    /// 以流的形式持续获取指定主题下的回复, 按需自动翻页.
+    pub fn get_topic_replies_stream(&self, topic_id: u32) -> Pages<TopicReply> {
+        let client = self.clone();
+        Pages::new(client, move |client, page| {
+            Box::pin(async move {
+                let rsp = client.get_topic_replies(&GetTopicRepliesReq { topic_id, page }).await?;
+                Ok(rsp.result)
+            })
+        })
+    }
+}
+
+/// [`Client`] 的构建器, 支持自定义 base URL、超时、user-agent, 或直接注入
+/// 一个已经配置好的 `reqwest::Client`, 便于测试时指向本地 mock 服务.
+#[derive(Default)]
+pub struct ClientBuilder {
+    token: Option<String>,
+    base_url: Option<String>,
+    timeout: Option<Duration>,
+    user_agent: Option<String>,
+    reqwest_client: Option<reqwest::Client>,
+}
+
+impl ClientBuilder {
+    pub fn new() -> ClientBuilder {
+        ClientBuilder::default()
+    }
+
+    /// 设置用于 `Authorization: Bearer <token>` 的访问令牌.
+    pub fn token(mut self, token: &str) -> ClientBuilder {
+        self.token = Some(token.to_string());
+        self
+    }
+
+    /// 覆盖默认的 [`V2EX_API_DOMAIN`], 例如指向一个本地 mock 服务器.
+    pub fn base_url(mut self, base_url: &str) -> ClientBuilder {
+        self.base_url = Some(base_url.to_string());
+        self
+    }
+
+    /// 设置单次请求的超时时间.
+    pub fn timeout(mut self, timeout: Duration) -> ClientBuilder {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// 设置 `User-Agent` 请求头.
+    pub fn user_agent(mut self, user_agent: &str) -> ClientBuilder {
+        self.user_agent = Some(user_agent.to_string());
+        self
+    }
+
+    /// 直接注入一个已经配置好的 `reqwest::Client`, 跳过 token/timeout/user-agent 的组装.
+    pub fn reqwest_client(mut self, reqwest_client: reqwest::Client) -> ClientBuilder {
+        self.reqwest_client = Some(reqwest_client);
+        self
+    }
+
+    pub fn build(self) -> Result<Client, ApiError> {
+        let req_client = match self.reqwest_client {
+            Some(req_client) => req_client,
+            None => {
+                let mut cb = reqwest::ClientBuilder::new();
+
+                if let Some(token) = &self.token {
+                    let mut bearer = String::from("Bearer ");
+                    bearer.push_str(token);
+
+                    let mut hm = HeaderMap::new();
+                    hm.append("Authorization", bearer.parse()?);
+                    cb = cb.default_headers(hm);
+                }
+
+                if let Some(timeout) = self.timeout {
+                    cb = cb.timeout(timeout);
+                }
+
+                if let Some(user_agent) = &self.user_agent {
+                    cb = cb.user_agent(user_agent);
+                }
+
+                cb.build()?
+            }
+        };
+
+        Ok(Client {
+            req_client,
+            base_url: self.base_url.unwrap_or_else(|| V2EX_API_DOMAIN.to_string()),
+            limit: Arc::new(AtomicU16::new(0)),
+            remaining: Arc::new(AtomicU16::new(0)),
+            reset: Arc::new(AtomicI64::new(0)),
+            rate_limiting: Arc::new(AtomicBool::new(false)),
+        })
+    }
+}
+
+/// 惰性分页拉取的异步流, 按需自动翻页, 直至某一页为空或请求失败.
+pub struct Pages<T> {
+    client: Client,
+    next_page: u32,
+    make_request: Box<dyn Fn(Client, u32) -> PageFuture<T> + Send>,
+    buffer: VecDeque<T>,
+    future: Option<PageFuture<T>>,
+    done: bool,
+}
+
+type PageFuture<T> = Pin<Box<dyn Future<Output = Result<Vec<T>, ApiError>> + Send>>;
+
+impl<T> Pages<T> {
+    fn new<F>(client: Client, make_request: F) -> Pages<T>
+    where
+        F: Fn(Client, u32) -> PageFuture<T> + Send + 'static,
+    {
+        Pages {
+            client,
+            next_page: 1,
+            make_request: Box::new(make_request),
+            buffer: VecDeque::new(),
+            future: None,
+            done: false,
+        }
+    }
+}
+
+impl<T: Unpin> Stream for Pages<T> {
+    type Item = Result<T, ApiError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        loop {
+            if let Some(item) = this.buffer.pop_front() {
+                return Poll::Ready(Some(Ok(item)));
+            }
+
+            if this.done {
+                return Poll::Ready(None);
+            }
+
+            if this.future.is_none() {
+                let client = this.client.clone();
+                this.future = Some((this.make_request)(client, this.next_page));
+            }
+
+            match this.future.as_mut().unwrap().as_mut().poll(cx) {
+                Poll::Pending => return Poll::Pending,
+                Poll::Ready(Err(e)) => {
+                    this.future = None;
+                    this.done = true;
+                    return Poll::Ready(Some(Err(e)));
+                }
+                Poll::Ready(Ok(items)) => {
+                    this.future = None;
+                    if items.is_empty() {
+                        this.done = true;
+                        continue;
+                    }
+                    this.next_page += 1;
+                    this.buffer.extend(items);
+                }
+            }
+        }
+    }
+}
+
+/// 在后台轮询 `/notifications` 并以推送的方式通知新提醒.
+///
+/// `Watcher` 本身不持有任何状态, 也不会被实例化: [`Watcher::spawn`] 启动一个
+/// tokio 任务并只返回 channel 接收端, 任务本身不需要被持有: 一旦返回的接收端
+/// 被丢弃, 任务会在下一次发送时自然退出.
+pub struct Watcher;
+
+impl Watcher {
+    /// 一轮轮询最多向后翻这么多页去找齐新提醒, 避免在离线太久后无休止地翻页.
+    const MAX_PAGES_PER_POLL: u32 = 10;
+
+    /// 以 `interval` 为基础轮询间隔启动后台任务, 仅推送此前未见过的新提醒.
+    ///
+    /// 轮询间隔会参考 [`Client::remaining`] 自动变宽: 剩余配额越接近 0, 实际
+    /// 等待时间越长, 从而在限流逼近时主动让出请求配额.
+    pub fn spawn(client: Client, interval: Duration) -> mpsc::Receiver<Notification> {
+        let (tx, rx) = mpsc::channel(32);
+
+        tokio::spawn(async move {
+            let mut last_seen: Option<u32> = None;
+
+            loop {
+                if let Ok((fresh, newest_seen)) = Self::poll_new(&client, last_seen).await {
+                    if newest_seen.is_some() {
+                        last_seen = newest_seen;
+                    }
+
+                    for notification in fresh {
+                        if tx.send(notification).await.is_err() {
+                            return;
+                        }
+                    }
+                }
+
+                tokio::time::sleep(Self::next_wait(&client, interval)).await;
+            }
+        });
+
+        rx
+    }
+
+    /// 获取自上次见过的 `last_seen` 之后的全部新提醒 (按从旧到新排列), 以及
+    /// 本轮见到的最新提醒 id. 首次轮询 (`last_seen` 为 `None`) 只建立基线,
+    /// 不回放历史提醒; 此后每轮最多向后翻 [`Self::MAX_PAGES_PER_POLL`] 页,
+    /// 直至遇到 `last_seen` 或拿到空页.
+    async fn poll_new(
+        client: &Client,
+        last_seen: Option<u32>,
+    ) -> Result<(Vec<Notification>, Option<u32>), ApiError> {
+        let last_id = match last_seen {
+            Some(last_id) => last_id,
+            None => {
+                let rsp = client.get_notifications(&GetNotificationsReq { page: 1 }).await?;
+                return Ok((Vec::new(), rsp.result.first().map(|n| n.id)));
+            }
+        };
+
+        let mut fresh = Vec::new();
+        let mut newest_seen = None;
+
+        for page in 1..=Self::MAX_PAGES_PER_POLL {
+            let rsp = client.get_notifications(&GetNotificationsReq { page }).await?;
+            if rsp.result.is_empty() {
+                break;
+            }
+
+            if newest_seen.is_none() {
+                newest_seen = Some(rsp.result[0].id);
+            }
+
+            let mut reached_last_seen = false;
+            for notification in rsp.result {
+                if notification.id <= last_id {
+                    reached_last_seen = true;
+                    break;
+                }
+                fresh.push(notification);
+            }
+
+            if reached_last_seen {
+                break;
+            }
+        }
+
+        fresh.reverse();
+        Ok((fresh, newest_seen))
+    }
+
+    /// 剩余配额越接近 0, 等待时间越长, 最多拉伸到 `base` 的 4 倍.
+    fn next_wait(client: &Client, base: Duration) -> Duration {
+        let limit = client.limit();
+        if limit == 0 {
+            return base;
+        }
+
+        let remaining = client.remaining();
+        let ratio = remaining as f64 / limit as f64;
+
+        if ratio <= 0.1 {
+            base * 4
+        } else if ratio <= 0.3 {
+            base * 2
+        } else {
+            base
+        }
+    }
 }
 
 pub struct GetTopicRepliesReq {
@@ -444,8 +832,18 @@ pub struct GetNotificationsReq {
 pub struct GetNotificationsRsp {
     #[serde(flatten)]
     pub status: Status,
-    // todo: 没有数据, 无法定义
-    // pub result: Vec<?>,
+    pub result: Vec<Notification>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Notification {
+    pub id: u32,
+    pub member: Member,
+    pub for_member: Member,
+    pub text: String,
+    pub payload: String,
+    pub payload_rendered: String,
+    pub created: i64,
 }
 
 /// 请求处理通用状态.
@@ -468,6 +866,221 @@ mod tests {
         Client::new(token.as_str())
     }
 
+    #[tokio::test]
+    async fn pages_stream_buffers_pages_and_stops_on_empty_page() {
+        use futures::StreamExt;
+
+        let call_count = Arc::new(AtomicU16::new(0));
+        let counted = call_count.clone();
+
+        let client = Client::new("token");
+        let mut pages: Pages<i32> = Pages::new(client, move |_client, page| {
+            let call_count = counted.clone();
+            Box::pin(async move {
+                call_count.fetch_add(1, Ordering::Relaxed);
+                match page {
+                    1 => Ok(vec![1, 2]),
+                    2 => Ok(vec![3]),
+                    _ => Ok(vec![]),
+                }
+            })
+        });
+
+        let mut items = Vec::new();
+        while let Some(item) = pages.next().await {
+            items.push(item.unwrap());
+        }
+
+        assert_eq!(items, vec![1, 2, 3]);
+        assert_eq!(call_count.load(Ordering::Relaxed), 3);
+        assert!(pages.next().await.is_none());
+    }
+
+    #[test]
+    fn with_rate_limiting_toggles_the_flag() {
+        let client = Client::new("token").with_rate_limiting(true);
+        assert!(client.rate_limiting.load(Ordering::Relaxed));
+
+        let client = client.with_rate_limiting(false);
+        assert!(!client.rate_limiting.load(Ordering::Relaxed));
+    }
+
+    #[test]
+    fn client_builder_overrides_base_url() {
+        let client = ClientBuilder::new()
+            .base_url("http://localhost:8080/api/v2")
+            .build()
+            .unwrap();
+        assert_eq!(client.base_url, "http://localhost:8080/api/v2");
+    }
+
+    #[test]
+    fn client_builder_defaults_to_v2ex_domain() {
+        let client = ClientBuilder::new().build().unwrap();
+        assert_eq!(client.base_url, V2EX_API_DOMAIN);
+    }
+
+    #[test]
+    fn client_builder_rejects_invalid_token_header() {
+        let err = ClientBuilder::new().token("bad\ntoken").build().unwrap_err();
+        assert!(matches!(err, ApiError::InvalidHeader(_)));
+    }
+
+    #[test]
+    fn next_wait_widens_as_remaining_quota_shrinks() {
+        let client = Client::new("token");
+        let base = Duration::from_secs(10);
+
+        // no rate-limit headers observed yet (limit == 0) -> base interval.
+        assert_eq!(Watcher::next_wait(&client, base), base);
+
+        client.limit.store(100, Ordering::Relaxed);
+
+        client.remaining.store(80, Ordering::Relaxed);
+        assert_eq!(Watcher::next_wait(&client, base), base);
+
+        client.remaining.store(20, Ordering::Relaxed);
+        assert_eq!(Watcher::next_wait(&client, base), base * 2);
+
+        client.remaining.store(5, Ordering::Relaxed);
+        assert_eq!(Watcher::next_wait(&client, base), base * 4);
+    }
+
+    fn dummy_notification(id: u32) -> Notification {
+        Notification {
+            id,
+            member: dummy_member(),
+            for_member: dummy_member(),
+            text: String::new(),
+            payload: String::new(),
+            payload_rendered: String::new(),
+            created: 0,
+        }
+    }
+
+    fn dummy_member() -> Member {
+        Member {
+            id: 1,
+            username: "tester".to_string(),
+            url: String::new(),
+            website: None,
+            twitter: None,
+            psn: None,
+            github: None,
+            btc: None,
+            location: None,
+            tagline: None,
+            bio: None,
+            avatar: None,
+            avatar_mini: None,
+            avatar_normal: None,
+            avatar_large: None,
+            created: 0,
+            last_modified: None,
+        }
+    }
+
+    /// 启动一个本地 mock `/notifications` 服务, 按页码依次返回 `pages` 中对应的
+    /// 提醒列表 (页码从 1 开始, 越界则返回空页). 返回 base URL 以及请求次数计数器,
+    /// 供测试断言 `poll_new` 实际发出的请求数.
+    async fn spawn_notifications_mock(pages: Vec<Vec<Notification>>) -> (String, Arc<AtomicU16>) {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        use tokio::net::TcpListener;
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let request_count = Arc::new(AtomicU16::new(0));
+        let counter = request_count.clone();
+        let pages = Arc::new(pages);
+
+        tokio::spawn(async move {
+            loop {
+                let (mut socket, _) = match listener.accept().await {
+                    Ok(pair) => pair,
+                    Err(_) => return,
+                };
+                let pages = pages.clone();
+                let counter = counter.clone();
+
+                tokio::spawn(async move {
+                    let mut buf = [0u8; 4096];
+                    let n = socket.read(&mut buf).await.unwrap_or(0);
+                    let request_line = String::from_utf8_lossy(&buf[..n]);
+
+                    let page = request_line
+                        .split_whitespace()
+                        .nth(1)
+                        .and_then(|path| path.split("p=").nth(1))
+                        .and_then(|p| p.split('&').next())
+                        .and_then(|p| p.parse::<u32>().ok())
+                        .unwrap_or(1);
+                    counter.fetch_add(1, Ordering::Relaxed);
+
+                    let empty = Vec::new();
+                    let result = pages.get((page - 1) as usize).unwrap_or(&empty);
+                    let body = serde_json::json!({
+                        "success": true,
+                        "message": "",
+                        "result": result,
+                    }).to_string();
+                    let response = format!(
+                        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        body.len(),
+                        body
+                    );
+                    let _ = socket.write_all(response.as_bytes()).await;
+                });
+            }
+        });
+
+        (base_url, request_count)
+    }
+
+    #[tokio::test]
+    async fn poll_new_first_poll_establishes_baseline_without_replay() {
+        let (base_url, requests) = spawn_notifications_mock(vec![
+            vec![dummy_notification(13), dummy_notification(12)],
+        ]).await;
+        let client = ClientBuilder::new().base_url(&base_url).build().unwrap();
+
+        let (fresh, newest_seen) = Watcher::poll_new(&client, None).await.unwrap();
+
+        assert!(fresh.is_empty());
+        assert_eq!(newest_seen, Some(13));
+        assert_eq!(requests.load(Ordering::Relaxed), 1);
+    }
+
+    #[tokio::test]
+    async fn poll_new_walks_pages_until_last_seen_boundary() {
+        let (base_url, requests) = spawn_notifications_mock(vec![
+            vec![dummy_notification(13), dummy_notification(12), dummy_notification(11)],
+            vec![dummy_notification(10), dummy_notification(9), dummy_notification(8)],
+        ]).await;
+        let client = ClientBuilder::new().base_url(&base_url).build().unwrap();
+
+        let (fresh, newest_seen) = Watcher::poll_new(&client, Some(9)).await.unwrap();
+
+        assert_eq!(fresh.iter().map(|n| n.id).collect::<Vec<_>>(), vec![10, 11, 12, 13]);
+        assert_eq!(newest_seen, Some(13));
+        assert_eq!(requests.load(Ordering::Relaxed), 2);
+    }
+
+    #[tokio::test]
+    async fn poll_new_caps_out_at_max_pages_per_poll() {
+        let pages: Vec<Vec<Notification>> = (0..20)
+            .map(|page| vec![dummy_notification(1000 - page)])
+            .collect();
+        let (base_url, requests) = spawn_notifications_mock(pages).await;
+        let client = ClientBuilder::new().base_url(&base_url).build().unwrap();
+
+        // last_seen is never hit within MAX_PAGES_PER_POLL pages, so the walk
+        // must stop at the cap instead of paging on forever.
+        let (fresh, _newest_seen) = Watcher::poll_new(&client, Some(0)).await.unwrap();
+
+        assert_eq!(fresh.len(), Watcher::MAX_PAGES_PER_POLL as usize);
+        assert_eq!(requests.load(Ordering::Relaxed), Watcher::MAX_PAGES_PER_POLL as u16);
+    }
+
     #[tokio::test]
     async fn get_notifications() {
         let c = new();